@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+
+/// How many soundex codes two artists share, and how many
+/// each of them has on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimilarSounds {
+    pub shared: usize,
+    pub coldplay_only: usize,
+    pub taylor_only: usize,
+}
+
+pub fn find_similar_words(
+    coldplay_lyrics: &HashMap<String, usize>,
+    taylor_lyrics: &HashMap<String, usize>,
+) -> SimilarSounds {
+    let coldplay_soundex = coldplay_lyrics
+        .keys()
+        .map(|s| soundex::american_soundex(s))
+        .collect::<HashSet<_>>();
+    let taylor_soundex = taylor_lyrics
+        .keys()
+        .map(|s| soundex::american_soundex(s))
+        .collect::<HashSet<_>>();
+
+    SimilarSounds {
+        shared: coldplay_soundex.intersection(&taylor_soundex).count(),
+        coldplay_only: coldplay_soundex.difference(&taylor_soundex).count(),
+        taylor_only: taylor_soundex.difference(&coldplay_soundex).count(),
+    }
+}
+
+/// The really common words (used more than 100 times, and longer
+/// than 4 letters) in `shard`'s part of the lyrics, out of `shards`.
+///
+/// Each shard takes its own run of each artist's words, in the order
+/// their maps iterate in, so every word is looked at by exactly one
+/// shard. That only holds while the maps aren't changed in between.
+///
+/// When both artists' words are merged, every word ends up with its
+/// Taylor Swift count (or 0), so that's the only count looked up.
+pub fn find_common_words_in_shard(
+    coldplay_lyrics: &HashMap<String, usize>,
+    taylor_lyrics: &HashMap<String, usize>,
+    shard: usize,
+    shards: usize,
+) -> Vec<String> {
+    let taylor_only = shard_keys(taylor_lyrics, shard, shards)
+        .filter(|word| !coldplay_lyrics.contains_key(*word));
+
+    shard_keys(coldplay_lyrics, shard, shards)
+        .chain(taylor_only)
+        .filter(|word| word.len() > 4 && *taylor_lyrics.get(*word).unwrap_or(&0) > 100)
+        .cloned()
+        .collect()
+}
+
+/// The keys of `map` that `shard` (out of `shards`) looks after.
+fn shard_keys(
+    map: &HashMap<String, usize>,
+    shard: usize,
+    shards: usize,
+) -> impl Iterator<Item = &String> + '_ {
+    let len = map.len();
+    let start = shard * len / shards;
+    let end = (shard + 1) * len / shards;
+    map.keys().skip(start).take(end - start)
+}
+
+/// The average length of every word sung, weighted by how often
+/// each word is used.
+///
+/// There's no average if there aren't any words, so that's `None`.
+pub fn average_word_length(lyrics: &HashMap<String, usize>) -> Option<f64> {
+    let length: usize = lyrics.iter().map(|(key, val)| key.len() * val).sum();
+    let words: usize = lyrics.values().sum();
+
+    (words > 0).then(|| length as f64 / words as f64)
+}
@@ -0,0 +1,101 @@
+/// How much the binary should print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only the analysis results.
+    Quiet,
+    /// The results, and any time a task waited on a lock that was
+    /// being written to, or asked to write to one (with `lock-audit`).
+    ///
+    /// Without the `lock-audit` feature, this prints exactly the same
+    /// thing as `Quiet`.
+    #[default]
+    Normal,
+    /// Also where the lyrics were read from and how long the run took
+    /// (on stderr), every lock hold, and readers that overlapped.
+    Verbose,
+    /// Also every task as it starts and finishes.
+    VeryVerbose,
+}
+
+/// Everything that can be set from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Options {
+    pub verbosity: Verbosity,
+    /// Print stable, tab-separated lines instead of sentences.
+    pub porcelain: bool,
+    /// How many io-bound tasks (e.g. loading a file) may start each second.
+    pub io_rate: Option<f64>,
+    /// Print [`USAGE`] and [`HELP`], and do nothing else.
+    pub help: bool,
+}
+
+pub const USAGE: &str = "usage: ws08 [-q | -v | -vv] [--porcelain] [--io-rate <ops/sec>]";
+
+/// Printed after [`USAGE`] for `--help`.
+pub const HELP: &str = "\
+Compares the lyrics of Taylor Swift and Coldplay.
+
+options:
+  -q, --quiet           only print the results
+  -v, --verbose         also say where the lyrics are read from (-vv: and every task)
+  --porcelain           print stable, tab-separated lines for scripts
+  --io-rate <ops/sec>   limit how many lyric files are opened each second
+  -h, --help            print this help
+
+The lyrics are read from ./data, or $WS08_DATA_DIR if it's set.";
+
+impl Options {
+    /// Parses the command line arguments (not including the program name).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut quiet = false;
+        let mut verbose = 0;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    options.help = true;
+                    return Ok(options);
+                }
+                "-q" | "--quiet" => quiet = true,
+                "--verbose" => verbose += 1,
+                "--porcelain" => options.porcelain = true,
+                "--io-rate" => {
+                    let rate = args.next().ok_or("--io-rate needs a number of ops/sec")?;
+                    options.io_rate = Some(parse_io_rate(&rate)?);
+                }
+                flag if flag.starts_with("--io-rate=") => {
+                    options.io_rate = Some(parse_io_rate(&flag["--io-rate=".len()..])?);
+                }
+                // -v, -vv, -vvv...
+                flags
+                    if flags.len() > 1
+                        && flags
+                            .strip_prefix('-')
+                            .is_some_and(|vs| vs.chars().all(|c| c == 'v')) =>
+                {
+                    verbose += flags.len() - 1;
+                }
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+
+        options.verbosity = match (quiet, verbose) {
+            (true, 0) => Verbosity::Quiet,
+            (true, _) => return Err("can't be both quiet and verbose".to_string()),
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::VeryVerbose,
+        };
+        Ok(options)
+    }
+}
+
+/// A rate limit has to be a positive, finite number of ops/sec.
+fn parse_io_rate(rate: &str) -> Result<f64, String> {
+    match rate.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("--io-rate must be a positive number, not {rate:?}")),
+    }
+}
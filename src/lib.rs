@@ -0,0 +1,10 @@
+pub mod analysis;
+pub mod cli;
+pub mod lock;
+pub mod lyrics;
+pub mod pipeline;
+pub mod prereq;
+pub mod rate_limit;
+pub mod reduce;
+pub mod report;
+pub mod scheduler;
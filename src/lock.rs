@@ -0,0 +1,191 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(feature = "lock-audit")]
+use std::{sync::Mutex, thread, time::Instant};
+
+#[cfg(feature = "lock-audit")]
+use crate::report::Report;
+
+/// An `RwLock` shared between tasks.
+///
+/// With the `lock-audit` feature turned on, it also records which
+/// task held it, for how long, and who had to wait.
+pub struct SharedLock<T> {
+    lock: RwLock<T>,
+    #[cfg(feature = "lock-audit")]
+    name: &'static str,
+    #[cfg(feature = "lock-audit")]
+    events: Mutex<Vec<LockEvent>>,
+}
+
+/// One time a task held a [`SharedLock`].
+#[cfg(feature = "lock-audit")]
+#[derive(Debug, Clone)]
+pub struct LockEvent {
+    pub task: String,
+    pub write: bool,
+    pub requested: Instant,
+    pub acquired: Instant,
+    pub released: Instant,
+}
+
+impl<T> SharedLock<T> {
+    #[cfg_attr(not(feature = "lock-audit"), allow(unused_variables))]
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            lock: RwLock::new(value),
+            #[cfg(feature = "lock-audit")]
+            name,
+            #[cfg(feature = "lock-audit")]
+            events: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        #[cfg(feature = "lock-audit")]
+        let requested = Instant::now();
+        ReadGuard {
+            guard: self.lock.read().unwrap(),
+            #[cfg(feature = "lock-audit")]
+            _recording: Recording::new(self, false, requested),
+        }
+    }
+
+    pub fn write(&self) -> WriteGuard<'_, T> {
+        #[cfg(feature = "lock-audit")]
+        let requested = Instant::now();
+        WriteGuard {
+            guard: self.lock.write().unwrap(),
+            #[cfg(feature = "lock-audit")]
+            _recording: Recording::new(self, true, requested),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.lock.into_inner().unwrap()
+    }
+}
+
+#[cfg(feature = "lock-audit")]
+impl<T> SharedLock<T> {
+    /// Every time this lock has been held so far.
+    pub fn events(&self) -> Vec<LockEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Reports how long each task held this lock, and every time one
+    /// task asked for it while another was still holding it.
+    pub fn audit(&self) -> Vec<Report> {
+        let mut events = self.events();
+        events.sort_by_key(|event| event.acquired);
+
+        let mut reports = events
+            .iter()
+            .map(|event| Report::LockHeld {
+                lock: self.name.to_string(),
+                task: event.task.clone(),
+                write: event.write,
+                held: event.released - event.acquired,
+            })
+            .collect::<Vec<_>>();
+
+        for holder in &events {
+            for contender in &events {
+                if holder.task == contender.task
+                    || contender.requested < holder.acquired
+                    || contender.requested >= holder.released
+                {
+                    continue;
+                }
+                reports.push(Report::LockContention {
+                    lock: self.name.to_string(),
+                    holder: holder.task.clone(),
+                    holder_write: holder.write,
+                    contender: contender.task.clone(),
+                    contender_write: contender.write,
+                    waited: contender.acquired - contender.requested,
+                    overlap: holder.released.min(contender.released) - contender.requested,
+                });
+            }
+        }
+        reports
+    }
+}
+
+impl<T: Default> Default for SharedLock<T> {
+    fn default() -> Self {
+        Self::new("unnamed", T::default())
+    }
+}
+
+/// Records a [`LockEvent`] when it's dropped.
+#[cfg(feature = "lock-audit")]
+struct Recording<'a> {
+    events: &'a Mutex<Vec<LockEvent>>,
+    write: bool,
+    requested: Instant,
+    acquired: Instant,
+}
+
+#[cfg(feature = "lock-audit")]
+impl<'a> Recording<'a> {
+    fn new<T>(lock: &'a SharedLock<T>, write: bool, requested: Instant) -> Self {
+        Self {
+            events: &lock.events,
+            write,
+            requested,
+            acquired: Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "lock-audit")]
+impl Drop for Recording<'_> {
+    fn drop(&mut self) {
+        let task = thread::current().name().unwrap_or("<unnamed>").to_string();
+        self.events.lock().unwrap().push(LockEvent {
+            task,
+            write: self.write,
+            requested: self.requested,
+            acquired: self.acquired,
+            released: Instant::now(),
+        });
+    }
+}
+
+// The lock guard is declared first, so it's released before the
+// recording notes down when that happened.
+pub struct ReadGuard<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+    #[cfg(feature = "lock-audit")]
+    _recording: Recording<'a>,
+}
+
+pub struct WriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    #[cfg(feature = "lock-audit")]
+    _recording: Recording<'a>,
+}
+
+impl<T> Deref for ReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> Deref for WriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for WriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Reads every file in the directory at `path`, and counts how many
+/// times each (lowercased, letters-only) word appears.
+pub fn get_lyric_frequency(path: impl AsRef<Path>) -> io::Result<HashMap<String, usize>> {
+    Ok(get_files_frequency(&lyric_files(path)?))
+}
+
+/// Counts the words across all of `files`, as if they were one file.
+pub fn get_files_frequency(files: &[PathBuf]) -> HashMap<String, usize> {
+    let mut lyrics: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        merge_frequencies(&mut lyrics, get_file_frequency(file));
+    }
+    lyrics
+}
+
+/// Every file in the directory at `path`, in a stable order.
+/// Subdirectories (and anything else that isn't a plain file) are skipped.
+///
+/// The error says which directory couldn't be read.
+pub fn lyric_files(path: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    let with_path = |e: io::Error| {
+        io::Error::new(
+            e.kind(),
+            format!("couldn't read lyrics from {}: {e}", path.display()),
+        )
+    };
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path).map_err(with_path)? {
+        let entry = entry.map_err(with_path)?;
+        if entry.file_type().map_err(with_path)?.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Counts the words in a single file of lyrics.
+///
+/// Bytes that aren't valid UTF-8 can't be letters, so they're dropped
+/// along with the rest of the punctuation.
+pub fn get_file_frequency(path: impl AsRef<Path>) -> HashMap<String, usize> {
+    let mut lyrics: HashMap<String, usize> = HashMap::new();
+    let contents = fs::read(path).unwrap();
+    let contents = String::from_utf8_lossy(&contents);
+    let new_contents = contents
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .filter(|c| c.is_ascii_lowercase() || c.is_whitespace())
+        .collect::<String>();
+    new_contents.split_ascii_whitespace().for_each(|c| {
+        *lyrics.entry(c.to_string()).or_default() += 1;
+    });
+    lyrics
+}
+
+/// Adds every count in `other` onto `lyrics`.
+pub fn merge_frequencies(lyrics: &mut HashMap<String, usize>, other: HashMap<String, usize>) {
+    for (word, count) in other {
+        *lyrics.entry(word).or_default() += count;
+    }
+}
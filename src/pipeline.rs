@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{average_word_length, find_common_words_in_shard, find_similar_words};
+use crate::lock::SharedLock;
+use crate::lyrics::{get_files_frequency, lyric_files};
+use crate::prereq::prereq;
+use crate::reduce::{add_tree_reduction, PartialMaps};
+use crate::report::{Report, ReportSink};
+use crate::scheduler::{Prerequisites, Scheduler, Task, TaskResult};
+
+/// Where each artist's lyrics live on disk.
+pub struct Corpora {
+    pub taylor: PathBuf,
+    pub coldplay: PathBuf,
+}
+
+/// Set this to point the binary at a different data directory.
+pub const DATA_DIR_VAR: &str = "WS08_DATA_DIR";
+
+impl Corpora {
+    /// The corpora as laid out in the `data` directory of this repo.
+    pub fn in_dir(root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        Self {
+            taylor: root.join("taylor-lyrics"),
+            coldplay: root.join("coldplay-lyrics"),
+        }
+    }
+
+    /// Looks in `$WS08_DATA_DIR` if it's set, and `./data` otherwise.
+    ///
+    /// Relative paths are left as they are, so they're resolved against
+    /// the current directory when the lyrics are read.
+    pub fn from_env() -> Self {
+        let root = env::var_os(DATA_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("data"));
+        Self::in_dir(root)
+    }
+}
+
+/// The word frequencies the loading tasks fill in, and the
+/// analysis tasks read from.
+pub struct Context {
+    pub taylor_lyrics: SharedLock<HashMap<String, usize>>,
+    pub coldplay_lyrics: SharedLock<HashMap<String, usize>>,
+    pub partials: PartialMaps,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            taylor_lyrics: SharedLock::new("taylor_lyrics", HashMap::new()),
+            coldplay_lyrics: SharedLock::new("coldplay_lyrics", HashMap::new()),
+            partials: PartialMaps::default(),
+        }
+    }
+}
+
+#[cfg(feature = "lock-audit")]
+impl Context {
+    /// Sends what the lock audit saw to `sink`.
+    pub fn report_lock_audit(&self, sink: &dyn ReportSink) {
+        for report in self
+            .taylor_lyrics
+            .audit()
+            .into_iter()
+            .chain(self.coldplay_lyrics.audit())
+        {
+            sink.report(report);
+        }
+    }
+}
+
+/// How many tasks the common words analysis is split between.
+pub const COMMON_WORDS_SHARDS: usize = 4;
+
+/// Tasks should happen in this order:
+///
+/// Scan in T. Swift --> Build word frequency hashmap -----\    /- Find the words that sound most similar.
+///                                                         ---< - Get the most common words.
+/// Scan in Coldplay --> Build word frequency hashmap -----/    \- Find, on average, how long the words of each artist are.
+///
+/// The corpus directories are listed straight away, so this fails if
+/// either of them can't be read.
+pub fn add_tasks<'a>(
+    scheduler: &mut Scheduler<'a>,
+    corpora: &'a Corpora,
+    context: &'a Context,
+    sink: &'a dyn ReportSink,
+) -> io::Result<()> {
+    let taylor_files = lyric_files(&corpora.taylor)?;
+    let coldplay_files = lyric_files(&corpora.coldplay)?;
+
+    scheduler.add_task(Task {
+        name: "load taylor".to_string(),
+        prerequisites: HashSet::new(),
+        io_bound: true,
+        task: Box::new(move || {
+            let mut taylor_lyrics = context.taylor_lyrics.write();
+            *taylor_lyrics = get_files_frequency(&taylor_files);
+            TaskResult::Finished(HashSet::from([prereq(Prerequisites::LoadedTSwift)]))
+        }),
+    });
+
+    scheduler.add_task(Task {
+        name: "load coldplay".to_string(),
+        prerequisites: HashSet::new(),
+        io_bound: true,
+        task: Box::new(move || {
+            let mut coldplay_lyrics = context.coldplay_lyrics.write();
+            *coldplay_lyrics = get_files_frequency(&coldplay_files);
+            TaskResult::Finished(HashSet::from([prereq(Prerequisites::LoadedColdplay)]))
+        }),
+    });
+
+    add_analysis_tasks(scheduler, context, sink);
+    Ok(())
+}
+
+/// The same as [`add_tasks`], except every file is loaded by its own
+/// task, and the results are merged together pairwise.
+pub fn add_per_file_tasks<'a>(
+    scheduler: &mut Scheduler<'a>,
+    corpora: &'a Corpora,
+    context: &'a Context,
+    sink: &'a dyn ReportSink,
+) -> io::Result<()> {
+    let taylor_files = lyric_files(&corpora.taylor)?;
+    let coldplay_files = lyric_files(&corpora.coldplay)?;
+
+    add_tree_reduction(
+        scheduler,
+        "taylor",
+        taylor_files,
+        &context.partials,
+        &context.taylor_lyrics,
+        Prerequisites::LoadedTSwift,
+    );
+    add_tree_reduction(
+        scheduler,
+        "coldplay",
+        coldplay_files,
+        &context.partials,
+        &context.coldplay_lyrics,
+        Prerequisites::LoadedColdplay,
+    );
+
+    add_analysis_tasks(scheduler, context, sink);
+    Ok(())
+}
+
+fn add_analysis_tasks<'a>(
+    scheduler: &mut Scheduler<'a>,
+    context: &'a Context,
+    sink: &'a dyn ReportSink,
+) {
+    let prereqs = HashSet::from([
+        prereq(Prerequisites::LoadedColdplay),
+        prereq(Prerequisites::LoadedTSwift),
+    ]);
+
+    // find_similar_words
+    scheduler.add_task(Task {
+        name: "find_similar_words".to_string(),
+        prerequisites: prereqs.clone(),
+        io_bound: false,
+        task: Box::new(|| {
+            let sounds = find_similar_words(
+                &context.coldplay_lyrics.read(),
+                &context.taylor_lyrics.read(),
+            );
+            sink.report(Report::SimilarSounds(sounds));
+
+            TaskResult::Finished(HashSet::new())
+        }),
+    });
+
+    // find_common_words
+    scheduler.add_fan_out(
+        "find_common_words",
+        prereqs.clone(),
+        COMMON_WORDS_SHARDS,
+        |i| {
+            find_common_words_in_shard(
+                &context.coldplay_lyrics.read(),
+                &context.taylor_lyrics.read(),
+                i,
+                COMMON_WORDS_SHARDS,
+            )
+        },
+        |shards| {
+            let mut words = shards.concat();
+            words.sort();
+            sink.report(Report::CommonWords(words));
+
+            TaskResult::Finished(HashSet::new())
+        },
+    );
+
+    // average_word_length
+    scheduler.add_task(Task {
+        name: "average_word_length".to_string(),
+        prerequisites: prereqs,
+        io_bound: false,
+        task: Box::new(|| {
+            // An artist with no lyrics has no average to report.
+            let lyrics = [
+                ("coldplay", &context.coldplay_lyrics),
+                ("taylor swift", &context.taylor_lyrics),
+            ];
+            for (artist, lyrics) in lyrics {
+                if let Some(average) = average_word_length(&lyrics.read()) {
+                    sink.report(Report::AverageWordLength {
+                        artist: artist.to_string(),
+                        average,
+                    });
+                }
+            }
+            TaskResult::Finished(HashSet::new())
+        }),
+    });
+}
@@ -0,0 +1,96 @@
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+
+/// Anything that can be used as a prerequisite.
+///
+/// This is implemented for every `Eq + Hash + Clone` type, so each
+/// module (or crate) can bring its own enum of events, and they can
+/// all be mixed together in one scheduler. Two keys are only ever
+/// equal if they're the same type.
+pub trait PrereqKey: Any + Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn dyn_eq(&self, other: &dyn PrereqKey) -> bool;
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+    fn clone_box(&self) -> Box<dyn PrereqKey>;
+}
+
+impl<K> PrereqKey for K
+where
+    K: Any + Debug + Eq + Hash + Clone + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn PrereqKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<K>().hash(&mut state);
+        self.hash(&mut state);
+    }
+
+    fn clone_box(&self) -> Box<dyn PrereqKey> {
+        Box::new(self.clone())
+    }
+}
+
+/// A prerequisite of any [`PrereqKey`] type.
+pub struct Prereq {
+    key: Box<dyn PrereqKey>,
+}
+
+impl Prereq {
+    /// Wraps up `key`. A `Prereq` is a key too, but wrapping one up
+    /// again just gives it back, so it's still equal to the key inside.
+    pub fn new(key: impl PrereqKey) -> Self {
+        match key.as_any().downcast_ref::<Prereq>() {
+            Some(prereq) => prereq.clone(),
+            None => Self { key: Box::new(key) },
+        }
+    }
+
+    /// The key inside, so it can be downcast back to its own type.
+    pub fn key(&self) -> &dyn PrereqKey {
+        &*self.key
+    }
+}
+
+impl PartialEq for Prereq {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.dyn_eq(&*other.key)
+    }
+}
+
+impl Eq for Prereq {}
+
+impl Hash for Prereq {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.dyn_hash(state);
+    }
+}
+
+impl Clone for Prereq {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone_box(),
+        }
+    }
+}
+
+impl Debug for Prereq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+/// A set of prerequisites, possibly of many different types.
+pub type PrereqSet = HashSet<Prereq>;
+
+/// Wraps up `key`, so it can go in a [`PrereqSet`].
+pub fn prereq(key: impl PrereqKey) -> Prereq {
+    Prereq::new(key)
+}
@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A token bucket: tokens drip in at `ops_per_sec`, and every
+/// operation has to take one out before it can go ahead.
+///
+/// The bucket holds one second's worth of tokens, or a single token
+/// if that's less than one, so an idle limiter allows a short burst
+/// and nothing more.
+pub struct RateLimiter {
+    ops_per_sec: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// # Panics
+    ///
+    /// If `ops_per_sec` isn't a positive, finite number.
+    pub fn new(ops_per_sec: f64) -> Self {
+        assert!(
+            ops_per_sec.is_finite() && ops_per_sec > 0.0,
+            "rate limit must be positive and finite, not {ops_per_sec}"
+        );
+        let capacity = ops_per_sec.max(1.0);
+        Self {
+            ops_per_sec,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Blocks until a token is available, then takes it.
+    pub fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let now = Instant::now();
+                let refill =
+                    now.duration_since(bucket.last_refill).as_secs_f64() * self.ops_per_sec;
+                bucket.tokens = (bucket.tokens + refill).min(self.capacity);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.ops_per_sec)
+            };
+            thread::sleep(wait);
+        }
+    }
+}
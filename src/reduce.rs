@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::lock::SharedLock;
+use crate::lyrics::{get_file_frequency, merge_frequencies};
+use crate::prereq::{prereq, PrereqKey};
+use crate::scheduler::{Scheduler, Task, TaskResult};
+
+/// The word frequencies for some of a corpus' files have been
+/// merged together.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct FilesLoaded {
+    pub corpus: String,
+    pub files: Range<usize>,
+}
+
+/// Frequency maps that cover some of a corpus' files, waiting to be
+/// merged with their neighbours.
+///
+/// The map for files `a..b` is kept under `(corpus, a)`.
+#[derive(Default)]
+pub struct PartialMaps {
+    maps: Mutex<HashMap<(String, usize), HashMap<String, usize>>>,
+}
+
+impl PartialMaps {
+    fn put(&self, corpus: &str, start: usize, map: HashMap<String, usize>) {
+        let replaced = self
+            .maps
+            .lock()
+            .unwrap()
+            .insert((corpus.to_string(), start), map);
+        assert!(
+            replaced.is_none(),
+            "partial map for {corpus}[{start}..] was produced twice"
+        );
+    }
+
+    fn take(&self, corpus: &str, start: usize) -> HashMap<String, usize> {
+        self.maps
+            .lock()
+            .unwrap()
+            .remove(&(corpus.to_string(), start))
+            .unwrap_or_else(|| panic!("partial map for {corpus}[{start}..] was never produced"))
+    }
+}
+
+/// Adds one loading task per file, then pairwise merge tasks until a
+/// single map covers every file.
+///
+/// That map ends up in `target`, and `done` is finished once it's there.
+///
+/// Every reduction sharing `partials` needs its own `corpus` name.
+pub fn add_tree_reduction<'a>(
+    scheduler: &mut Scheduler<'a>,
+    corpus: &'a str,
+    files: Vec<PathBuf>,
+    partials: &'a PartialMaps,
+    target: &'a SharedLock<HashMap<String, usize>>,
+    done: impl PrereqKey,
+) {
+    let done = prereq(done);
+    let empty = files.is_empty();
+    let mut prerequisites = HashSet::new();
+    if !empty {
+        prerequisites.insert(prereq(add_merge_tasks(
+            scheduler,
+            corpus,
+            &files,
+            0..files.len(),
+            partials,
+        )));
+    }
+
+    scheduler.add_task(Task {
+        name: format!("collect {corpus}"),
+        prerequisites,
+        io_bound: false,
+        task: Box::new(move || {
+            // With no files, there's nothing to merge.
+            *target.write() = if empty {
+                HashMap::new()
+            } else {
+                partials.take(corpus, 0)
+            };
+            TaskResult::Finished(HashSet::from([done.clone()]))
+        }),
+    });
+}
+
+/// Adds the tasks that build the map for `range`, and returns the
+/// prerequisite that will be finished once it's ready.
+fn add_merge_tasks<'a>(
+    scheduler: &mut Scheduler<'a>,
+    corpus: &'a str,
+    files: &[PathBuf],
+    range: Range<usize>,
+    partials: &'a PartialMaps,
+) -> FilesLoaded {
+    let finished = FilesLoaded {
+        corpus: corpus.to_string(),
+        files: range.clone(),
+    };
+
+    let start = range.start;
+    let new_prereqs = HashSet::from([prereq(finished.clone())]);
+
+    if range.len() == 1 {
+        let file = files[start].clone();
+        scheduler.add_task(Task {
+            name: format!("load {corpus}[{start}]"),
+            prerequisites: HashSet::new(),
+            io_bound: true,
+            task: Box::new(move || {
+                partials.put(corpus, start, get_file_frequency(&file));
+                TaskResult::Finished(new_prereqs.clone())
+            }),
+        });
+        return finished;
+    }
+
+    let mid = start + range.len() / 2;
+    let left = add_merge_tasks(scheduler, corpus, files, start..mid, partials);
+    let right = add_merge_tasks(scheduler, corpus, files, mid..range.end, partials);
+
+    scheduler.add_task(Task {
+        name: format!("merge {corpus}[{range:?}]"),
+        prerequisites: HashSet::from([prereq(left), prereq(right)]),
+        io_bound: false,
+        task: Box::new(move || {
+            let mut lyrics = partials.take(corpus, start);
+            merge_frequencies(&mut lyrics, partials.take(corpus, mid));
+            partials.put(corpus, start, lyrics);
+            TaskResult::Finished(new_prereqs.clone())
+        }),
+    });
+    finished
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::analysis::SimilarSounds;
+use crate::cli::Verbosity;
+
+/// A single result produced by one of the analysis tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Report {
+    SimilarSounds(SimilarSounds),
+    CommonWords(Vec<String>),
+    AverageWordLength {
+        artist: String,
+        average: f64,
+    },
+    /// A task held a shared lock (only with the `lock-audit` feature).
+    LockHeld {
+        lock: String,
+        task: String,
+        write: bool,
+        held: Duration,
+    },
+    /// A task asked for a shared lock while another task held it
+    /// (only with the `lock-audit` feature).
+    LockContention {
+        lock: String,
+        holder: String,
+        holder_write: bool,
+        contender: String,
+        contender_write: bool,
+        waited: Duration,
+        overlap: Duration,
+    },
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Report::SimilarSounds(sounds) => {
+                writeln!(
+                    f,
+                    "Coldplay and Taylor Swift have {} similar sounds.",
+                    sounds.shared
+                )?;
+                writeln!(f, "Coldplay has {} unique sounds.", sounds.coldplay_only)?;
+                write!(f, "Taylor Swift has {} unique sounds.", sounds.taylor_only)
+            }
+            Report::CommonWords(words) => {
+                for (i, word) in words.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "A really common word is: {word}")?;
+                }
+                Ok(())
+            }
+            Report::AverageWordLength { artist, average } => {
+                write!(f, "Average {} word length: {}", artist, average)
+            }
+            Report::LockHeld {
+                lock,
+                task,
+                write,
+                held,
+            } => write!(f, "{task} held {lock} ({}) for {held:?}", mode(*write)),
+            Report::LockContention {
+                lock,
+                holder,
+                holder_write,
+                contender,
+                contender_write,
+                waited,
+                overlap,
+            } => write!(
+                f,
+                "{contender} asked for {lock} ({}) while {holder} held it ({}): \
+                 waited {waited:?}, overlapped for {overlap:?}",
+                mode(*contender_write),
+                mode(*holder_write),
+            ),
+        }
+    }
+}
+
+impl Report {
+    /// The least verbosity this report should be printed at.
+    pub fn verbosity(&self) -> Verbosity {
+        match self {
+            Report::SimilarSounds(_)
+            | Report::CommonWords(_)
+            | Report::AverageWordLength { .. } => Verbosity::Quiet,
+            // Readers don't block each other, so they're only worth
+            // mentioning when someone asks for everything.
+            Report::LockContention {
+                holder_write: false,
+                contender_write: false,
+                ..
+            } => Verbosity::Verbose,
+            Report::LockContention { .. } => Verbosity::Normal,
+            Report::LockHeld { .. } => Verbosity::Verbose,
+        }
+    }
+}
+
+/// Displays a report as tab-separated lines, for scripts to read.
+///
+/// Every line starts with the kind of record it is; durations are
+/// in whole nanoseconds. This format should only ever be added to.
+pub struct Porcelain<'a>(pub &'a Report);
+
+impl fmt::Display for Porcelain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Report::SimilarSounds(sounds) => {
+                writeln!(f, "similar_sounds\tshared\t{}", sounds.shared)?;
+                writeln!(f, "similar_sounds\tcoldplay_only\t{}", sounds.coldplay_only)?;
+                write!(f, "similar_sounds\ttaylor_only\t{}", sounds.taylor_only)
+            }
+            Report::CommonWords(words) => {
+                for (i, word) in words.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "common_word\t{word}")?;
+                }
+                Ok(())
+            }
+            Report::AverageWordLength { artist, average } => {
+                write!(f, "average_word_length\t{artist}\t{average}")
+            }
+            Report::LockHeld {
+                lock,
+                task,
+                write,
+                held,
+            } => write!(
+                f,
+                "lock_held\t{lock}\t{task}\t{}\t{}",
+                mode(*write),
+                held.as_nanos()
+            ),
+            Report::LockContention {
+                lock,
+                holder,
+                holder_write,
+                contender,
+                contender_write,
+                waited,
+                overlap,
+            } => write!(
+                f,
+                "lock_contention\t{lock}\t{holder}\t{}\t{contender}\t{}\t{}\t{}",
+                mode(*holder_write),
+                mode(*contender_write),
+                waited.as_nanos(),
+                overlap.as_nanos()
+            ),
+        }
+    }
+}
+
+/// How a lock was held.
+fn mode(write: bool) -> &'static str {
+    if write {
+        "write"
+    } else {
+        "read"
+    }
+}
+
+/// Somewhere the analysis tasks can send their results.
+///
+/// Tasks run on their own threads, so a sink has to be shareable
+/// between them.
+pub trait ReportSink: Sync {
+    fn report(&self, report: Report);
+}
+
+/// Prints every report to stdout, unless it's too verbose.
+///
+/// Tasks finish in whatever order they like, so reports are held on
+/// to until [`PrintSink::finish`], and then printed in the same order
+/// every time: the analysis results first, then the lock audit.
+#[derive(Default)]
+pub struct PrintSink {
+    pub verbosity: Verbosity,
+    pub porcelain: bool,
+    reports: Mutex<Vec<Report>>,
+}
+
+impl PrintSink {
+    pub fn new(verbosity: Verbosity, porcelain: bool) -> Self {
+        Self {
+            verbosity,
+            porcelain,
+            reports: Mutex::default(),
+        }
+    }
+
+    /// Prints everything that's been reported.
+    pub fn finish(self) {
+        let mut reports = self.reports.into_inner().unwrap();
+        reports.sort_by_key(print_order);
+
+        // If stdout has gone away (e.g. we're piped into `head`), there's
+        // nobody left to tell, so don't bring the whole run down.
+        let mut stdout = io::stdout().lock();
+        for report in reports {
+            let _ = if self.porcelain {
+                writeln!(stdout, "{}", Porcelain(&report))
+            } else {
+                writeln!(stdout, "{report}")
+            };
+        }
+    }
+}
+
+/// Where each kind of report goes in [`PrintSink`]'s output. Reports
+/// of the same kind stay in the order they arrived.
+fn print_order(report: &Report) -> usize {
+    match report {
+        Report::SimilarSounds(_) => 0,
+        Report::CommonWords(_) => 1,
+        Report::AverageWordLength { .. } => 2,
+        Report::LockHeld { .. } => 3,
+        Report::LockContention { .. } => 4,
+    }
+}
+
+impl ReportSink for PrintSink {
+    fn report(&self, report: Report) {
+        if report.verbosity() > self.verbosity {
+            return;
+        }
+        if let Report::CommonWords(words) = &report {
+            if words.is_empty() {
+                return;
+            }
+        }
+        self.reports.lock().unwrap().push(report);
+    }
+}
+
+/// Collects reports, so they can be inspected after the scheduler finishes.
+impl ReportSink for Mutex<Vec<Report>> {
+    fn report(&self, report: Report) {
+        self.lock().unwrap().push(report);
+    }
+}
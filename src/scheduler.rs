@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread::ScopedJoinHandle;
+use std::time::Instant;
+
+use crate::prereq::{prereq, PrereqSet};
+use crate::rate_limit::RateLimiter;
+
+/// This is a list of every "event" that can happen in our
+/// pipeline.
+///
+/// Other modules can have their own prerequisite types (see
+/// [`crate::prereq::PrereqKey`]); they all live side by side.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Prerequisites {
+    LoadedTSwift,
+    LoadedColdplay,
+}
+
+#[allow(dead_code)]
+pub enum TaskResult {
+    Finished(PrereqSet),
+    RunMeAgain,
+}
+
+/// This is a particular task that needs to be run.
+///
+/// A task has "prerequisites" -- it can't run until
+/// they have happened.
+///
+/// The `name` is given to the thread the task runs on, so it shows
+/// up in panics (and lock audits).
+///
+/// Tasks that are `io_bound` also have to wait for the scheduler's
+/// rate limiter (if it has one) before they run.
+// #[derive(Clone)]
+pub struct Task<'a> {
+    pub name: String,
+    pub prerequisites: PrereqSet,
+    pub io_bound: bool,
+    pub task: Box<dyn FnMut() -> TaskResult + Send + 'a>,
+}
+
+/// One shard of a fan-out (see [`Scheduler::add_fan_out`]) has finished.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct ShardDone {
+    fan_out: usize,
+    shard: usize,
+}
+
+/// This contains all the tasks, and also all the prerequisites
+/// that have already happened.
+pub struct Scheduler<'a> {
+    tasks: Vec<Task<'a>>,
+    prerequisites: PrereqSet,
+    io_rate_limiter: Option<RateLimiter>,
+    fan_outs: usize,
+    trace: bool,
+}
+
+impl<'a> Scheduler<'a> {
+    pub fn start(mut self) {
+        loop {
+            if self.tasks.is_empty() {
+                break;
+            }
+
+            let (mut to_parallelise, others): (Vec<_>, Vec<_>) = self.tasks
+                .into_iter()
+                .partition(|task| self.prerequisites.is_superset(&task.prerequisites));
+
+            self.tasks = others;
+            // Anything that isn't rate limited gets going first, so it
+            // doesn't wait behind the io-bound tasks.
+            to_parallelise.sort_by_key(|task| task.io_bound);
+
+            let io_rate_limiter = self.io_rate_limiter.as_ref();
+            let trace = self.trace;
+
+            std::thread::scope(|s| {
+                let results: Vec<ScopedJoinHandle<TaskResult>> = to_parallelise
+                    .into_iter()
+                    .map(|mut task| {
+                        // Waiting here, rather than in the task's thread, means
+                        // there's only ever one thread waiting on the limiter.
+                        if let (true, Some(limiter)) = (task.io_bound, io_rate_limiter) {
+                            limiter.acquire();
+                        }
+                        std::thread::Builder::new()
+                            .name(task.name.clone())
+                            .spawn_scoped(s, move || {
+                                if trace {
+                                    eprintln!("started {}", task.name);
+                                }
+                                let started = Instant::now();
+                                let result = (task.task)();
+                                if trace {
+                                    let elapsed = started.elapsed();
+                                    eprintln!("finished {} in {:?}", task.name, elapsed);
+                                }
+                                result
+                            })
+                            .unwrap()
+                    })
+                    .collect();
+
+                for result in results {
+                    // Pass a task's panic on as it is, rather than burying
+                    // it under one of our own.
+                    let result = result
+                        .join()
+                        .unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+                    if let TaskResult::Finished(new_prereqs) = result {
+                        self.prerequisites.extend(new_prereqs);
+                    }
+                }
+            })
+        }
+    }
+
+    pub fn add_task(&mut self, task: Task<'a>) {
+        self.tasks.push(task);
+    }
+
+    /// Splits some work into `shards` tasks, which can all run at the
+    /// same time once `prerequisites` have happened.
+    ///
+    /// When every shard is done, their results are handed (in order)
+    /// to `merge`, which finishes like any other task.
+    pub fn add_fan_out<T, S, M>(
+        &mut self,
+        name: &str,
+        prerequisites: PrereqSet,
+        shards: usize,
+        shard: S,
+        merge: M,
+    ) where
+        T: Send + 'a,
+        S: Fn(usize) -> T + Send + Sync + 'a,
+        M: FnOnce(Vec<T>) -> TaskResult + Send + 'a,
+    {
+        let fan_out = self.fan_outs;
+        self.fan_outs += 1;
+
+        let results = Arc::new(Mutex::new((0..shards).map(|_| None).collect::<Vec<_>>()));
+        let shard = Arc::new(shard);
+        let mut merge_prereqs = prerequisites.clone();
+
+        for i in 0..shards {
+            let results = Arc::clone(&results);
+            let shard = Arc::clone(&shard);
+            let done = prereq(ShardDone { fan_out, shard: i });
+            merge_prereqs.insert(done.clone());
+
+            self.add_task(Task {
+                name: format!("{name}[{i}]"),
+                prerequisites: prerequisites.clone(),
+                io_bound: false,
+                task: Box::new(move || {
+                    let result = shard(i);
+                    results.lock().unwrap()[i] = Some(result);
+                    TaskResult::Finished(HashSet::from([done.clone()]))
+                }),
+            });
+        }
+
+        let mut merge = Some(merge);
+        self.add_task(Task {
+            name: name.to_string(),
+            prerequisites: merge_prereqs,
+            io_bound: false,
+            task: Box::new(move || {
+                let results = results
+                    .lock()
+                    .unwrap()
+                    .drain(..)
+                    .map(|result| result.expect("every shard has finished"))
+                    .collect();
+                (merge.take().expect("a fan-out only merges once"))(results)
+            }),
+        });
+    }
+
+    /// Only let `ops_per_sec` io-bound tasks start each second.
+    ///
+    /// # Panics
+    ///
+    /// If `ops_per_sec` isn't a positive, finite number.
+    pub fn set_io_rate_limit(&mut self, ops_per_sec: f64) {
+        self.io_rate_limiter = Some(RateLimiter::new(ops_per_sec));
+    }
+
+    /// Print (to stderr) every task as it starts and finishes.
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    pub fn new() -> Self {
+        Self {
+            tasks: vec![],
+            prerequisites: PrereqSet::new(),
+            io_rate_limiter: None,
+            fan_outs: 0,
+            trace: false,
+        }
+    }
+}
+
+impl Default for Scheduler<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,98 @@
+use std::process::Command;
+use std::time::Duration;
+
+use ws08::analysis::SimilarSounds;
+use ws08::cli::{Options, Verbosity};
+use ws08::report::{Porcelain, Report};
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn verbosity_flags() {
+    assert_eq!(parse(&[]).unwrap().verbosity, Verbosity::Normal);
+    assert_eq!(parse(&["-q"]).unwrap().verbosity, Verbosity::Quiet);
+    assert_eq!(parse(&["-v"]).unwrap().verbosity, Verbosity::Verbose);
+    assert_eq!(parse(&["-vv"]).unwrap().verbosity, Verbosity::VeryVerbose);
+    assert_eq!(
+        parse(&["-v", "--verbose"]).unwrap().verbosity,
+        Verbosity::VeryVerbose
+    );
+    assert!(parse(&["-q", "-v"]).is_err());
+    assert!(parse(&["-x"]).is_err());
+    assert!(parse(&["-"]).is_err());
+    assert!(parse(&["--"]).is_err());
+
+    let options = parse(&["--porcelain", "-q"]).unwrap();
+    assert!(options.porcelain);
+    assert_eq!(options.verbosity, Verbosity::Quiet);
+}
+
+#[test]
+fn help_flag() {
+    assert!(parse(&["-h"]).unwrap().help);
+    assert!(parse(&["--porcelain", "--help", "--bogus"]).unwrap().help);
+    assert!(!parse(&[]).unwrap().help);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .arg("--help")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("usage: ws08"));
+}
+
+#[test]
+fn io_rate_flag() {
+    assert_eq!(parse(&[]).unwrap().io_rate, None);
+    assert_eq!(parse(&["--io-rate", "20"]).unwrap().io_rate, Some(20.0));
+    assert_eq!(parse(&["--io-rate=0.5"]).unwrap().io_rate, Some(0.5));
+    assert!(parse(&["--io-rate"]).is_err());
+    assert!(parse(&["--io-rate", "0"]).is_err());
+    assert!(parse(&["--io-rate", "NaN"]).is_err());
+    assert!(parse(&["--io-rate=inf"]).is_err());
+    assert!(parse(&["--io-rate=fast"]).is_err());
+}
+
+#[test]
+fn porcelain_is_tab_separated() {
+    let lines = |report: Report| Porcelain(&report).to_string();
+
+    assert_eq!(
+        lines(Report::SimilarSounds(SimilarSounds {
+            shared: 1,
+            coldplay_only: 2,
+            taylor_only: 3,
+        })),
+        "similar_sounds\tshared\t1\nsimilar_sounds\tcoldplay_only\t2\nsimilar_sounds\ttaylor_only\t3"
+    );
+    assert_eq!(
+        lines(Report::CommonWords(vec![
+            "shake".to_string(),
+            "there".to_string()
+        ])),
+        "common_word\tshake\ncommon_word\tthere"
+    );
+    assert_eq!(
+        lines(Report::AverageWordLength {
+            artist: "taylor swift".to_string(),
+            average: 3.5,
+        }),
+        "average_word_length\ttaylor swift\t3.5"
+    );
+    assert_eq!(
+        lines(Report::LockContention {
+            lock: "taylor_lyrics".to_string(),
+            holder: "load taylor".to_string(),
+            holder_write: true,
+            contender: "find_common_words[0]".to_string(),
+            contender_write: false,
+            waited: Duration::from_nanos(12),
+            overlap: Duration::from_micros(3),
+        }),
+        "lock_contention\ttaylor_lyrics\tload taylor\twrite\tfind_common_words[0]\tread\t12\t3000"
+    );
+}
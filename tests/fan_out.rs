@@ -0,0 +1,84 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ws08::analysis::find_common_words_in_shard;
+use ws08::prereq::prereq;
+use ws08::scheduler::{Scheduler, Task, TaskResult};
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct Summed;
+
+#[test]
+fn fan_out_merges_every_shard_in_order() {
+    let total = AtomicUsize::new(0);
+    let mut scheduler = Scheduler::new();
+
+    scheduler.add_fan_out(
+        "squares",
+        HashSet::new(),
+        5,
+        |shard| shard * shard,
+        |squares| {
+            assert_eq!(squares, vec![0, 1, 4, 9, 16]);
+            total.store(squares.iter().sum(), Ordering::SeqCst);
+            TaskResult::Finished(HashSet::from([prereq(Summed)]))
+        },
+    );
+    scheduler.add_task(Task {
+        name: "after".to_string(),
+        prerequisites: HashSet::from([prereq(Summed)]),
+        io_bound: false,
+        task: Box::new(|| {
+            assert_eq!(total.load(Ordering::SeqCst), 30);
+            total.store(0, Ordering::SeqCst);
+            TaskResult::Finished(HashSet::new())
+        }),
+    });
+    scheduler.start();
+
+    assert_eq!(total.load(Ordering::SeqCst), 0);
+}
+
+/// Finds the really common words all at once, by merging both
+/// artists' words the way the analysis is defined.
+fn find_common_words(
+    coldplay_lyrics: &HashMap<String, usize>,
+    taylor_lyrics: &HashMap<String, usize>,
+) -> Vec<String> {
+    let mut common_words = coldplay_lyrics.clone();
+    common_words.iter_mut().for_each(|(word, count)| {
+        *count = *taylor_lyrics.get(word).unwrap_or(&0);
+    });
+    for (word, count) in taylor_lyrics {
+        common_words.entry(word.clone()).or_insert(*count);
+    }
+
+    let mut words = common_words
+        .into_iter()
+        .filter(|(word, count)| *count > 100 && word.len() > 4)
+        .map(|(word, _)| word)
+        .collect::<Vec<_>>();
+    words.sort();
+    words
+}
+
+#[test]
+fn common_word_shards_cover_every_word_once() {
+    let coldplay = (0..30)
+        .map(|i| (format!("songs{i}"), 200))
+        .chain([("clocks".to_string(), 3), ("words7".to_string(), 1)])
+        .collect::<HashMap<_, _>>();
+    let taylor = (0..50)
+        .map(|i| (format!("words{i}"), 100 + i))
+        .chain([("clocks".to_string(), 500)])
+        .collect::<HashMap<_, _>>();
+
+    let mut sharded = (0..4)
+        .flat_map(|i| find_common_words_in_shard(&coldplay, &taylor, i, 4))
+        .collect::<Vec<_>>();
+    sharded.sort();
+
+    let expected = find_common_words(&coldplay, &taylor);
+    assert_eq!(expected.len(), 50);
+    assert_eq!(sharded, expected);
+}
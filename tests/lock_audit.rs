@@ -0,0 +1,88 @@
+#![cfg(feature = "lock-audit")]
+
+use std::sync::Barrier;
+use std::thread;
+use std::time::Duration;
+
+use ws08::cli::Verbosity;
+use ws08::lock::SharedLock;
+use ws08::report::Report;
+
+#[test]
+fn overlapping_readers_are_reported() {
+    let lock = SharedLock::new("lyrics", 0);
+    let both_reading = Barrier::new(2);
+
+    thread::scope(|s| {
+        for name in ["first", "second"] {
+            thread::Builder::new()
+                .name(name.to_string())
+                .spawn_scoped(s, || {
+                    if thread::current().name() == Some("second") {
+                        // Make sure "first" gets the lock before we do.
+                        both_reading.wait();
+                        let _guard = lock.read();
+                        both_reading.wait();
+                    } else {
+                        let _guard = lock.read();
+                        both_reading.wait();
+                        both_reading.wait();
+                    }
+                })
+                .unwrap();
+        }
+    });
+
+    let reports = lock.audit();
+    let held = reports
+        .iter()
+        .filter(|report| matches!(report, Report::LockHeld { write: false, .. }))
+        .count();
+    assert_eq!(held, 2);
+    let contention = reports
+        .iter()
+        .find(|report| matches!(report, Report::LockContention { .. }))
+        .unwrap();
+    assert!(matches!(
+        contention,
+        Report::LockContention { lock, holder, contender, holder_write: false, contender_write: false, .. }
+            if lock == "lyrics" && holder == "first" && contender == "second"
+    ));
+    assert_eq!(contention.verbosity(), Verbosity::Verbose);
+}
+
+#[test]
+fn waiting_on_a_writer_is_reported() {
+    let lock = SharedLock::new("lyrics", 0);
+    let writing = Barrier::new(2);
+
+    thread::scope(|s| {
+        thread::Builder::new()
+            .name("writer".to_string())
+            .spawn_scoped(s, || {
+                let _guard = lock.write();
+                writing.wait();
+                thread::sleep(Duration::from_millis(10));
+            })
+            .unwrap();
+        thread::Builder::new()
+            .name("reader".to_string())
+            .spawn_scoped(s, || {
+                writing.wait();
+                let _guard = lock.read();
+            })
+            .unwrap();
+    });
+
+    let contention = lock
+        .audit()
+        .into_iter()
+        .find(|report| matches!(report, Report::LockContention { .. }))
+        .unwrap();
+    assert!(matches!(
+        contention,
+        Report::LockContention { ref holder, holder_write: true, contender_write: false, waited, .. }
+            if holder == "writer" && waited > Duration::ZERO
+    ));
+    assert_eq!(contention.verbosity(), Verbosity::Normal);
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+use ws08::analysis::SimilarSounds;
+use ws08::pipeline::{self, Context, Corpora, DATA_DIR_VAR};
+use ws08::report::{Report, ReportSink};
+use ws08::scheduler::Scheduler;
+
+/// Writes each `(file name, contents)` pair into a fresh directory.
+fn write_corpus(dir: &Path, files: &[(&str, &str)]) {
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).unwrap();
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+}
+
+/// Lays out a small Taylor Swift and Coldplay corpus under `root`.
+fn write_sample_corpora(root: &Path) {
+    let shake = "shake ".repeat(101);
+    write_corpus(
+        &root.join("taylor-lyrics"),
+        &[
+            ("one.txt", &format!("{shake}\nnight")),
+            ("two.txt", "Night, night!"),
+        ],
+    );
+    write_corpus(
+        &root.join("coldplay-lyrics"),
+        &[("one.txt", "Yellow night\nyellow")],
+    );
+}
+
+fn run_pipeline(
+    name: &str,
+    add_tasks: for<'a> fn(
+        &mut Scheduler<'a>,
+        &'a Corpora,
+        &'a Context,
+        &'a dyn ReportSink,
+    ) -> io::Result<()>,
+) -> Vec<Report> {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    write_sample_corpora(&root);
+    let corpora = Corpora::in_dir(&root);
+    let context = Context::default();
+    let sink = Mutex::new(vec![]);
+    let mut scheduler = Scheduler::new();
+
+    add_tasks(&mut scheduler, &corpora, &context, &sink).unwrap();
+    scheduler.start();
+
+    sink.into_inner().unwrap()
+}
+
+fn assert_reports(reports: Vec<Report>) {
+    assert_eq!(reports.len(), 4);
+    assert!(reports.contains(&Report::SimilarSounds(SimilarSounds {
+        shared: 1,
+        coldplay_only: 1,
+        taylor_only: 1,
+    })));
+    assert!(reports.contains(&Report::CommonWords(vec!["shake".to_string()])));
+    assert!(reports.contains(&Report::AverageWordLength {
+        artist: "coldplay".to_string(),
+        average: 17.0 / 3.0,
+    }));
+    assert!(reports.contains(&Report::AverageWordLength {
+        artist: "taylor swift".to_string(),
+        average: 5.0,
+    }));
+}
+
+#[test]
+fn pipeline_reports_every_analysis() {
+    assert_reports(run_pipeline(
+        "pipeline_reports_every_analysis",
+        pipeline::add_tasks,
+    ));
+}
+
+#[test]
+fn per_file_pipeline_reports_every_analysis() {
+    assert_reports(run_pipeline(
+        "per_file_pipeline_reports_every_analysis",
+        pipeline::add_per_file_tasks,
+    ));
+}
+
+// These run the binary, so setting the data directory doesn't race
+// with any other test.
+
+#[test]
+fn data_dir_is_relative_to_the_current_directory() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("data_dir_is_relative");
+    write_sample_corpora(&root.join("corpus"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .arg("--porcelain")
+        .current_dir(&root)
+        .env(DATA_DIR_VAR, "corpus")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("average_word_length\ttaylor swift\t5\n"));
+}
+
+#[test]
+fn missing_data_dir_is_reported() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("missing_data_dir");
+    write_corpus(&root, &[]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .current_dir(&root)
+        .env_remove(DATA_DIR_VAR)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("couldn't read lyrics from data"));
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn stray_entries_in_a_corpus_are_not_fatal() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("stray_entries");
+    write_sample_corpora(&root);
+    let taylor = root.join("taylor-lyrics");
+    fs::create_dir(taylor.join("bonus-tracks")).unwrap();
+    fs::write(taylor.join("latin1.txt"), b"night\xff\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .arg("--porcelain")
+        .env(DATA_DIR_VAR, &root)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{stderr}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("average_word_length\ttaylor swift\t5\n"));
+}
+
+#[test]
+fn an_empty_corpus_has_no_average() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("empty_corpus_average");
+    write_sample_corpora(&root);
+    write_corpus(&root.join("coldplay-lyrics"), &[]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .arg("--porcelain")
+        .env(DATA_DIR_VAR, &root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("average_word_length\tcoldplay"));
+    assert!(stdout.contains("average_word_length\ttaylor swift\t5\n"));
+}
+
+#[test]
+fn porcelain_lines_come_out_in_a_fixed_order() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("porcelain_order");
+    write_sample_corpora(&root);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ws08"))
+        .args(["-q", "--porcelain"])
+        .env(DATA_DIR_VAR, &root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fields = stdout
+        .lines()
+        .map(|line| line.split('\t').take(2).collect::<Vec<_>>().join("\t"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            "similar_sounds\tshared",
+            "similar_sounds\tcoldplay_only",
+            "similar_sounds\ttaylor_only",
+            "common_word\tshake",
+            "average_word_length\tcoldplay",
+            "average_word_length\ttaylor swift",
+        ]
+    );
+}
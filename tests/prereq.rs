@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ws08::prereq::prereq;
+use ws08::scheduler::{Prerequisites, Scheduler, Task, TaskResult};
+
+/// Events that only this test knows about.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+enum Stage {
+    Loaded,
+}
+
+/// Looks just like `Stage`, but is a different type.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+enum OtherStage {
+    Loaded,
+}
+
+#[test]
+fn keys_of_different_types_are_never_equal() {
+    let stage = prereq(Stage::Loaded);
+    let set = HashSet::from([stage.clone(), prereq(Prerequisites::LoadedTSwift)]);
+
+    assert!(set.contains(&prereq(Stage::Loaded)));
+    assert!(!set.contains(&prereq(OtherStage::Loaded)));
+    assert_eq!(stage.key().as_any().downcast_ref(), Some(&Stage::Loaded));
+}
+
+#[test]
+fn wrapping_a_prereq_again_changes_nothing() {
+    let twice = prereq(prereq(Stage::Loaded));
+
+    assert_eq!(twice, prereq(Stage::Loaded));
+    assert_eq!(twice.key().as_any().downcast_ref(), Some(&Stage::Loaded));
+}
+
+#[test]
+fn user_prerequisites_mix_with_library_ones() {
+    let ran = AtomicBool::new(false);
+    let mut scheduler = Scheduler::new();
+
+    scheduler.add_task(Task {
+        name: "user".to_string(),
+        prerequisites: HashSet::new(),
+        io_bound: false,
+        task: Box::new(|| TaskResult::Finished(HashSet::from([prereq(Stage::Loaded)]))),
+    });
+    scheduler.add_task(Task {
+        name: "library".to_string(),
+        prerequisites: HashSet::new(),
+        io_bound: false,
+        task: Box::new(|| {
+            TaskResult::Finished(HashSet::from([prereq(Prerequisites::LoadedColdplay)]))
+        }),
+    });
+    scheduler.add_task(Task {
+        name: "both".to_string(),
+        prerequisites: HashSet::from([
+            prereq(Stage::Loaded),
+            prereq(Prerequisites::LoadedColdplay),
+        ]),
+        io_bound: false,
+        task: Box::new(|| {
+            ran.store(true, Ordering::SeqCst);
+            TaskResult::Finished(HashSet::new())
+        }),
+    });
+    scheduler.start();
+
+    assert!(ran.load(Ordering::SeqCst));
+}
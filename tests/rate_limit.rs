@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use ws08::scheduler::{Scheduler, Task, TaskResult};
+
+#[test]
+fn io_bound_tasks_wait_for_the_rate_limiter() {
+    let ran = AtomicUsize::new(0);
+    let mut scheduler = Scheduler::new();
+    scheduler.set_io_rate_limit(2.0);
+
+    for _ in 0..4 {
+        scheduler.add_task(Task {
+            name: "io".to_string(),
+            prerequisites: HashSet::new(),
+            io_bound: true,
+            task: Box::new(|| {
+                ran.fetch_add(1, Ordering::SeqCst);
+                TaskResult::Finished(HashSet::new())
+            }),
+        });
+    }
+
+    let start = Instant::now();
+    scheduler.start();
+
+    // Two tokens are ready straight away; the other two take half a second each.
+    assert!(start.elapsed() >= Duration::from_millis(900));
+    assert_eq!(ran.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn other_tasks_dont_wait_behind_io_bound_ones() {
+    let started = Instant::now();
+    let cpu_started = Mutex::new(None);
+    let mut scheduler = Scheduler::new();
+    scheduler.set_io_rate_limit(2.0);
+
+    for _ in 0..4 {
+        scheduler.add_task(Task {
+            name: "io".to_string(),
+            prerequisites: HashSet::new(),
+            io_bound: true,
+            task: Box::new(|| TaskResult::Finished(HashSet::new())),
+        });
+    }
+    scheduler.add_task(Task {
+        name: "cpu".to_string(),
+        prerequisites: HashSet::new(),
+        io_bound: false,
+        task: Box::new(|| {
+            *cpu_started.lock().unwrap() = Some(started.elapsed());
+            TaskResult::Finished(HashSet::new())
+        }),
+    });
+
+    scheduler.start();
+
+    let cpu_started = cpu_started.into_inner().unwrap().unwrap();
+    assert!(cpu_started < Duration::from_millis(400));
+}
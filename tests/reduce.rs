@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ws08::lock::SharedLock;
+use ws08::lyrics::{get_lyric_frequency, lyric_files};
+use ws08::reduce::{add_tree_reduction, PartialMaps};
+use ws08::scheduler::{Prerequisites, Scheduler};
+
+/// Writes `count` files, where file `i` has `i` copies of "word" and
+/// one of "common".
+fn generated_dir(name: &str, count: usize) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for i in 0..count {
+        let contents = format!("{} common\n", "word ".repeat(i));
+        fs::write(dir.join(format!("{i:02}.txt")), contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn tree_reduction_merges_every_file() {
+    let dir = generated_dir("tree_reduction_merges_every_file", 37);
+
+    let partials = PartialMaps::default();
+    let target = SharedLock::new("target", HashMap::new());
+    let mut scheduler = Scheduler::new();
+    add_tree_reduction(
+        &mut scheduler,
+        "generated",
+        lyric_files(&dir).unwrap(),
+        &partials,
+        &target,
+        Prerequisites::LoadedTSwift,
+    );
+    scheduler.start();
+
+    let lyrics = target.into_inner();
+    assert_eq!(lyrics, get_lyric_frequency(&dir).unwrap());
+    assert_eq!(lyrics["common"], 37);
+    assert_eq!(lyrics["word"], (0..37).sum::<usize>());
+}
+
+#[test]
+fn tree_reduction_of_nothing_is_empty() {
+    let partials = PartialMaps::default();
+    let target = SharedLock::new("target", HashMap::from([("stale".to_string(), 1)]));
+    let mut scheduler = Scheduler::new();
+    add_tree_reduction(
+        &mut scheduler,
+        "empty",
+        vec![],
+        &partials,
+        &target,
+        Prerequisites::LoadedTSwift,
+    );
+    scheduler.start();
+
+    assert!(target.into_inner().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "was produced twice")]
+fn reductions_sharing_a_corpus_name_are_caught() {
+    let dir = generated_dir("reductions_sharing_a_corpus_name_are_caught", 2);
+    let partials = PartialMaps::default();
+    let first = SharedLock::new("first", HashMap::new());
+    let second = SharedLock::new("second", HashMap::new());
+    let mut scheduler = Scheduler::new();
+    for (target, done) in [
+        (&first, Prerequisites::LoadedTSwift),
+        (&second, Prerequisites::LoadedColdplay),
+    ] {
+        add_tree_reduction(
+            &mut scheduler,
+            "same",
+            lyric_files(&dir).unwrap(),
+            &partials,
+            target,
+            done,
+        );
+    }
+    scheduler.start();
+}